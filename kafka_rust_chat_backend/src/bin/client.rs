@@ -0,0 +1,30 @@
+//! CLI entry point for `ChatClient`, the reconnecting WebSocket client for
+//! `kafka_rust_chat_backend`'s `/ws/:room` endpoint. Useful for load
+//! testing, bridging, and bots. See `src/client.rs` for the reusable
+//! client itself, which this crate's integration tests also drive
+//! directly.
+
+use kafka_rust_chat_backend::client::ChatClient;
+use std::env;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+#[tokio::main]
+async fn main() {
+    let url = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "ws://localhost:3001/ws/lobby".to_string());
+
+    let client = ChatClient::connect(url.clone(), |msg| println!("< {msg}"));
+    println!("client: ready on {url} (type a message and press enter, Ctrl+D to exit)");
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.is_empty() {
+            continue;
+        }
+        let chat = serde_json::json!({"type": "chat", "text": line}).to_string();
+        if client.send(chat).await.is_err() {
+            break;
+        }
+    }
+}
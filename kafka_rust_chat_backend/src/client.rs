@@ -0,0 +1,134 @@
+//! A reconnecting WebSocket client for `/ws/:room`.
+//!
+//! Used by the `client` binary for load testing, bridging, and bots, and
+//! by this crate's integration tests to drive a server end-to-end without
+//! relying on an external tool like `websocat`. Mirrors the server's own
+//! split-stream pattern: each connection is split into read and write
+//! halves, both driven from one background task by a `select!` loop (there
+//! are no separate reader and writer tasks), with automatic reconnection
+//! (exponential backoff) and a
+//! bounded outbox so messages sent while disconnected are buffered and
+//! flushed once the connection comes back.
+
+use crate::compression;
+use futures_util::{SinkExt, StreamExt};
+use std::{sync::Arc, time::Duration};
+use tokio::{net::TcpStream, sync::mpsc, time::sleep};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+const OUTBOX_CAPACITY: usize = 256;
+
+/// A reconnecting WebSocket client. While the connection to the server is
+/// down, [`ChatClient::send`] just queues into a bounded channel; nothing
+/// drains it until a connection is re-established, so messages sent while
+/// disconnected are flushed in order once the next connection comes up.
+/// A message already written to a connection the server has dropped,
+/// before the client notices the drop, is not resent.
+pub struct ChatClient {
+    outbox: mpsc::Sender<String>,
+}
+
+impl ChatClient {
+    /// Connects to `url` (e.g. `ws://localhost:3001/ws/lobby`) and spawns
+    /// a background task that maintains the connection for as long as the
+    /// `ChatClient` lives, reconnecting with exponential backoff whenever
+    /// it drops. Once the `ChatClient` is dropped, the task ends as soon as
+    /// it holds a live connection; while the server stays unreachable it
+    /// keeps redialing. `on_message` is invoked for every text (or
+    /// decompressed binary) frame the server sends.
+    pub fn connect<F>(url: String, on_message: F) -> Self
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        let (outbox_tx, outbox_rx) = mpsc::channel(OUTBOX_CAPACITY);
+        tokio::spawn(run(url, outbox_rx, Arc::new(on_message)));
+        Self { outbox: outbox_tx }
+    }
+
+    /// Queues `msg` for sending. Succeeds even while disconnected — it
+    /// will be flushed once a connection is established — and only fails
+    /// once the client's background task has shut down for good.
+    pub async fn send(&self, msg: String) -> Result<(), mpsc::error::SendError<String>> {
+        self.outbox.send(msg).await
+    }
+}
+
+/// Reconnection loop: keeps (re)dialing `url` with exponential backoff,
+/// handing each live connection to [`drive`] until it closes.
+async fn run(
+    url: String,
+    mut outbox_rx: mpsc::Receiver<String>,
+    on_message: Arc<dyn Fn(String) + Send + Sync>,
+) {
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        match connect_async(&url).await {
+            Ok((ws, _)) => {
+                println!("client: connected to {url}");
+                backoff = INITIAL_BACKOFF;
+                if !drive(ws, &mut outbox_rx, &on_message).await {
+                    return;
+                }
+                println!("client: disconnected from {url}, will retry");
+            }
+            Err(err) => {
+                eprintln!("client: failed to connect to {url}: {err}");
+            }
+        }
+        sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Drives one live connection: forwards outbox messages to the socket,
+/// sends a heartbeat ping on an interval, and invokes `on_message` for
+/// every server frame. Returns `true` once the socket closes or errors (so
+/// the caller redials), or `false` once the outbox is closed because the
+/// `ChatClient` was dropped.
+async fn drive(
+    ws: WsStream,
+    outbox_rx: &mut mpsc::Receiver<String>,
+    on_message: &Arc<dyn Fn(String) + Send + Sync>,
+) -> bool {
+    let (mut write, mut read) = ws.split();
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+
+    loop {
+        tokio::select! {
+            msg = outbox_rx.recv() => {
+                let Some(msg) = msg else { return false };
+                if write.send(Message::Text(msg)).await.is_err() {
+                    return true;
+                }
+            }
+            _ = heartbeat.tick() => {
+                if write.send(Message::Ping(Vec::new())).await.is_err() {
+                    return true;
+                }
+            }
+            incoming = read.next() => {
+                match incoming {
+                    Some(Ok(Message::Text(text))) => on_message(text),
+                    // The server only sends Binary for frames compressed
+                    // under a negotiated codec; undo that the same way the
+                    // server's own frame_to_text does.
+                    Some(Ok(Message::Binary(bytes))) => {
+                        if let Ok(decompressed) = compression::decompress(&bytes) {
+                            if let Ok(text) = String::from_utf8(decompressed) {
+                                on_message(text);
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => return true,
+                    Some(Err(_)) => return true,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::io::{Read, Write};
+
+/// Frame/payload compression codec: one server-wide codec for Kafka
+/// records, and one negotiated independently for each WebSocket connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codec {
+    None,
+    Zstd,
+    Gzip,
+}
+
+impl Codec {
+    /// Parses a client-advertised codec name, e.g. the `compress` query
+    /// param on the `/ws/:room` handshake. Anything unrecognized falls
+    /// back to no compression.
+    pub fn from_name(name: Option<&str>) -> Self {
+        match name {
+            Some("zstd") => Codec::Zstd,
+            Some("gzip") => Codec::Gzip,
+            _ => Codec::None,
+        }
+    }
+
+    /// The librdkafka `compression.codec` setting for this codec.
+    pub fn librdkafka_name(self) -> &'static str {
+        match self {
+            Codec::None => "none",
+            Codec::Zstd => "zstd",
+            Codec::Gzip => "gzip",
+        }
+    }
+}
+
+/// Tunable compression settings, grouped in one struct held in `AppState`
+/// rather than as bare consts, mirroring [`crate::dedup::DedupConfig`].
+/// The server starts from [`CompressionConfig::default`]; nothing reads
+/// these values from an env var or a config file.
+#[derive(Clone, Copy, Debug)]
+pub struct CompressionConfig {
+    /// Codec librdkafka and the manual Kafka-record compression both use.
+    /// Unlike the per-connection WebSocket codec, this one applies to
+    /// every record server-wide rather than being negotiated per client.
+    pub kafka_codec: Codec,
+    /// Payloads smaller than this are tagged raw by [`compress`] for both
+    /// Kafka records and WebSocket frames — not worth the CPU for a short
+    /// chat message or a typing indicator. librdkafka's own
+    /// `compression.codec` still applies to every Kafka record.
+    pub min_size: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            kafka_codec: Codec::Zstd,
+            min_size: 256,
+        }
+    }
+}
+
+/// Compresses `data` with `codec` when it's at least `min_size` bytes,
+/// prefixing the result with a one-byte marker (`0` raw, `1` zstd, `2`
+/// gzip) so [`decompress`] knows how to undo it. Payloads under
+/// `min_size` (e.g. typing/presence events) are tagged raw rather than
+/// paying the compression overhead.
+pub fn compress(codec: Codec, data: &[u8], min_size: usize) -> Vec<u8> {
+    if codec == Codec::None || data.len() < min_size {
+        return tag(0, data.to_vec());
+    }
+    match codec {
+        Codec::Zstd => tag(
+            1,
+            zstd::stream::encode_all(data, 0).unwrap_or_else(|_| data.to_vec()),
+        ),
+        Codec::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            let compressed = encoder
+                .write_all(data)
+                .and_then(|_| encoder.finish())
+                .unwrap_or_else(|_| data.to_vec());
+            tag(2, compressed)
+        }
+        Codec::None => unreachable!(),
+    }
+}
+
+/// Reverses [`compress`], reading the marker byte to pick the right
+/// decoder.
+pub fn decompress(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let (marker, body) = data
+        .split_first()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "empty frame"))?;
+    match marker {
+        0 => Ok(body.to_vec()),
+        1 => zstd::stream::decode_all(body),
+        2 => {
+            let mut decoder = flate2::read::GzDecoder::new(body);
+            let mut out = Vec::new();
+            decoder.read_to_end(&mut out)?;
+            Ok(out)
+        }
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "unknown compression marker",
+        )),
+    }
+}
+
+fn tag(marker: u8, mut body: Vec<u8>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len() + 1);
+    out.push(marker);
+    out.append(&mut body);
+    out
+}
@@ -0,0 +1,112 @@
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Configuration for [`DedupStore`]'s eviction window.
+#[derive(Clone, Copy, Debug)]
+pub struct DedupConfig {
+    /// How long an id is remembered before it's eligible for eviction.
+    pub ttl: Duration,
+    /// Cap on the number of remembered ids, regardless of age. It is
+    /// enforced before each insert, so right after an insert the store can
+    /// hold `capacity + 1` ids until the next call trims it.
+    pub capacity: usize,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            capacity: 10_000,
+        }
+    }
+}
+
+/// Tracks recently-seen message ids so duplicates delivered close together
+/// in time are filtered, without the set growing unbounded over a
+/// long-running process. Entries older than `ttl`, or beyond `capacity`,
+/// are evicted from the front of an insertion log in FIFO order.
+pub struct DedupStore {
+    config: DedupConfig,
+    seen: HashSet<String>,
+    log: VecDeque<(Instant, String)>,
+}
+
+impl DedupStore {
+    pub fn new(config: DedupConfig) -> Self {
+        Self {
+            config,
+            seen: HashSet::new(),
+            log: VecDeque::new(),
+        }
+    }
+
+    /// Evicts expired/overflowing entries, then records `id` and returns
+    /// `true` if it was not already present in the window, `false` if it
+    /// was (i.e. a duplicate).
+    pub fn insert_if_new(&mut self, id: &str) -> bool {
+        self.evict();
+        if !self.seen.insert(id.to_string()) {
+            return false;
+        }
+        self.log.push_back((Instant::now(), id.to_string()));
+        true
+    }
+
+    fn evict(&mut self) {
+        let now = Instant::now();
+        while let Some((inserted_at, _)) = self.log.front() {
+            let expired = now.duration_since(*inserted_at) > self.config.ttl;
+            let over_capacity = self.log.len() > self.config.capacity;
+            if !expired && !over_capacity {
+                break;
+            }
+            if let Some((_, id)) = self.log.pop_front() {
+                self.seen.remove(&id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_within_ttl_window_is_rejected() {
+        let mut store = DedupStore::new(DedupConfig {
+            ttl: Duration::from_secs(60),
+            capacity: 10,
+        });
+        assert!(store.insert_if_new("a"));
+        assert!(!store.insert_if_new("a"));
+    }
+
+    #[test]
+    fn entry_past_ttl_is_evicted_and_reusable() {
+        let mut store = DedupStore::new(DedupConfig {
+            ttl: Duration::from_millis(20),
+            capacity: 10,
+        });
+        assert!(store.insert_if_new("a"));
+        std::thread::sleep(Duration::from_millis(40));
+        // Eviction is lazy — it only runs on the next call — so "a" is
+        // still stale until this insert triggers it.
+        assert!(store.insert_if_new("a"));
+    }
+
+    #[test]
+    fn insertion_beyond_capacity_evicts_from_front() {
+        let mut store = DedupStore::new(DedupConfig {
+            ttl: Duration::from_secs(60),
+            capacity: 2,
+        });
+        assert!(store.insert_if_new("a"));
+        assert!(store.insert_if_new("b"));
+        // Pushes the log to 3 entries, one past capacity; eviction is
+        // lazy, so "a" isn't dropped until the *next* insert.
+        assert!(store.insert_if_new("c"));
+        // "a" was at the front (oldest) and capacity=2, so it's evicted to
+        // make room here and counts as new again.
+        assert!(store.insert_if_new("a"));
+    }
+}
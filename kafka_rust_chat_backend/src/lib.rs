@@ -0,0 +1,8 @@
+//! Shared modules for the `kafka_rust_chat_backend` binary and the
+//! `client` binary, and for this crate's integration tests (`tests/`).
+
+pub mod client;
+pub mod compression;
+pub mod dedup;
+pub mod protocol;
+pub mod transport;
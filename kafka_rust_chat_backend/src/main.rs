@@ -1,14 +1,11 @@
 use axum::{
-    extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
-    },
+    extract::{ws::WebSocketUpgrade, Path, Query, State},
     response::IntoResponse,
     routing::get,
     Router,
 };
 use chrono::Utc;
-use futures_util::{SinkExt, StreamExt};
+use futures_util::StreamExt;
 use rdkafka::{
     consumer::{Consumer, StreamConsumer},
     producer::{FutureProducer, FutureRecord},
@@ -16,23 +13,144 @@ use rdkafka::{
 };
 
 use std::time::Duration;
-use tokio::{sync::broadcast, task::JoinHandle};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    net::TcpListener,
+    sync::{broadcast, mpsc},
+    task::JoinHandle,
+};
 use uuid::Uuid;
 
 use std::{
-    collections::HashSet,
+    collections::HashMap,
     sync::{Arc, Mutex},
 };
 
+use kafka_rust_chat_backend::{
+    compression::{self, Codec, CompressionConfig},
+    dedup::{DedupConfig, DedupStore},
+    protocol::{ClientMessage, ServerMessage},
+    transport::{
+        Frame, TcpLineTransport, Transport, TransportReceiver, TransportSender,
+        WebSocketTransport,
+    },
+};
+
+/// Prefix shared by every per-room Kafka topic. The consumer subscribes to
+/// this as a regex pattern (librdkafka treats a leading `^` as a pattern
+/// subscription) so new rooms don't require re-subscribing.
+const ROOM_TOPIC_PREFIX: &str = "chat-room-";
+const ROOM_TOPIC_PATTERN: &str = "^chat-room-.*";
+
+/// Port the raw-TCP `Transport` backend listens on, alongside the
+/// WebSocket server. A connection's first line must be the room name;
+/// everything after is the same `ClientMessage` JSON, one per line, that
+/// WebSocket clients send as text frames.
+const TCP_PORT: u16 = 3002;
+
+/// Broadcast channels for every room currently in use, created lazily the
+/// first time a client (or the consumer) touches a room.
+type Rooms = Arc<Mutex<HashMap<String, broadcast::Sender<String>>>>;
+
+/// A single connected client's identity and the channel used to reach it
+/// directly, e.g. to deliver its own `Error` frames (see
+/// [`send_to_client`]).
+struct ClientHandle {
+    room: String,
+    // Set from a client's `Join` message; not read elsewhere yet, but kept
+    // on the registry entry for a future participant-listing endpoint.
+    #[allow(dead_code)]
+    name: Option<String>,
+    sender: mpsc::UnboundedSender<String>,
+}
+
+/// Every currently-connected client, keyed by its per-connection id.
+type Clients = Arc<Mutex<HashMap<Uuid, ClientHandle>>>;
+
+/// State shared across all Axum handlers.
+#[derive(Clone)]
+struct AppState {
+    rooms: Rooms,
+    clients: Clients,
+    producer: FutureProducer,
+    seen_messages: Arc<Mutex<DedupStore>>,
+    compression: CompressionConfig,
+}
+
+/// Returns the room's broadcast sender, creating one with capacity 100 if
+/// this is the first time the room has been seen.
+fn get_or_create_room(rooms: &Rooms, room: &str) -> broadcast::Sender<String> {
+    let mut rooms = rooms.lock().unwrap();
+    rooms
+        .entry(room.to_string())
+        .or_insert_with(|| broadcast::channel::<String>(100).0)
+        .clone()
+}
+
+fn room_topic(room: &str) -> String {
+    format!("{ROOM_TOPIC_PREFIX}{room}")
+}
+
+fn room_from_topic(topic: &str) -> Option<&str> {
+    topic.strip_prefix(ROOM_TOPIC_PREFIX)
+}
+
+/// Number of clients currently registered in `room`.
+fn participant_count(clients: &Clients, room: &str) -> usize {
+    clients
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|c| c.room == room)
+        .count()
+}
+
+/// Delivers `msg` straight to `client_id` via its registry entry, bypassing
+/// the room broadcast. A no-op if the client has already disconnected.
+fn send_to_client(clients: &Clients, client_id: Uuid, msg: String) {
+    if let Some(handle) = clients.lock().unwrap().get(&client_id) {
+        let _ = handle.sender.send(msg);
+    }
+}
+
+fn new_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+fn now_ts() -> String {
+    Utc::now().to_rfc3339()
+}
+
+/// Recovers the JSON text carried by a frame, decompressing binary frames
+/// per the one-byte marker from [`compression::compress`].
+fn frame_to_text(frame: Frame) -> Option<String> {
+    match frame {
+        Frame::Text(text) => Some(text),
+        Frame::Binary(bytes) => {
+            let decompressed = compression::decompress(&bytes).ok()?;
+            String::from_utf8(decompressed).ok()
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
-    // Create a broadcast channel for Strings with capacity 100
-    let (tx, _rx) = broadcast::channel::<String>(100);
+    // Rooms are created on demand; start with an empty map.
+    let rooms: Rooms = Arc::new(Mutex::new(HashMap::new()));
+
+    // No clients are connected yet; handle_socket registers/unregisters as
+    // connections come and go.
+    let clients: Clients = Arc::new(Mutex::new(HashMap::new()));
+
+    // Codec and size threshold for both Kafka records and WebSocket
+    // frames; see `CompressionConfig` for why these aren't bare consts.
+    let compression_config = CompressionConfig::default();
 
     // Create a Kafka producer connected to local Kafka broker
     // FutureProducer allows async message sending with awaitable results
     let producer: FutureProducer = ClientConfig::new()
         .set("bootstrap.servers", "localhost:9092")
+        .set("compression.codec", compression_config.kafka_codec.librdkafka_name())
         .create()
         .expect("Producer creation failed");
 
@@ -45,19 +163,21 @@ async fn main() {
         .create()
         .expect("Consumer creation failed");
 
-    // Subscribe to the "chat-room" Kafka topic to start receiving messages
-    // If subscription fails, the application cannot function properly
+    // Subscribe to every "chat-room-*" topic via a pattern subscription, so
+    // rooms created after startup are picked up without re-subscribing.
     consumer
-        .subscribe(&["chat-room"])
-        .expect("Can't subscribe to chat-room");
+        .subscribe(&[ROOM_TOPIC_PATTERN])
+        .expect("Can't subscribe to chat-room-* topics");
 
-    // Track processed message IDs to avoid duplicates
-    let seen_messages = Arc::new(Mutex::new(HashSet::<String>::new()));
+    // Track recently-processed message IDs to avoid duplicates, bounded to
+    // a rolling time window so memory doesn't grow forever.
+    let seen_messages = Arc::new(Mutex::new(DedupStore::new(DedupConfig::default())));
 
-    let tx_clone = tx.clone();
+    let rooms_clone = rooms.clone();
     let seen_clone = seen_messages.clone();
 
     // Background task: Consume Kafka messages, deduplicate, and broadcast
+    // each one to the room matching its topic.
     tokio::spawn(async move {
         // Create a stream of incoming Kafka messages
         let mut stream = consumer.stream();
@@ -65,15 +185,25 @@ async fn main() {
         // Continuously poll for new messages in an infinite loop
         while let Some(result) = stream.next().await {
             if let Ok(msg) = result {
-                if let Some(Ok(text)) = msg.payload_view::<str>() {
-                    if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
-                        if let Some(id) = value["id"].as_str() {
-                            // Critical section: Check and update seen messages
-                            let mut seen = seen_clone.lock().unwrap();
-                            if !seen.contains(id) {
-                                seen.insert(id.to_string());
-                                // println!("Kafka → {}", text);
-                                let _ = tx_clone.send(text.to_string()); // Fire-and-forget broadcast
+                let Some(room) = room_from_topic(msg.topic()) else {
+                    continue;
+                };
+                if let Some(raw) = msg.payload() {
+                    // Undo the manual payload compression before dedup/
+                    // broadcast so the rest of the pipeline keeps working
+                    // with plain JSON text.
+                    if let Ok(decompressed) = compression::decompress(raw) {
+                        if let Ok(text) = std::str::from_utf8(&decompressed) {
+                            if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(text) {
+                                // Critical section: check-and-insert against
+                                // the dedup window.
+                                let is_new =
+                                    seen_clone.lock().unwrap().insert_if_new(server_msg.id());
+                                if is_new {
+                                    // println!("Kafka → {}", text);
+                                    let tx = get_or_create_room(&rooms_clone, room);
+                                    let _ = tx.send(text.to_string()); // Fire-and-forget broadcast
+                                }
                             }
                         }
                     }
@@ -82,21 +212,55 @@ async fn main() {
         }
     });
 
+    let state = AppState {
+        rooms,
+        clients,
+        producer,
+        seen_messages,
+        compression: compression_config,
+    };
+
+    // Background task: accept raw-TCP clients alongside the WebSocket
+    // server, via the same `handle_socket` fan-in/fan-out logic. TCP
+    // clients never negotiate compression, so they're always driven with
+    // `Codec::None`.
+    let tcp_state = state.clone();
+    tokio::spawn(async move {
+        let listener = TcpListener::bind(("0.0.0.0", TCP_PORT))
+            .await
+            .expect("TCP transport bind failed");
+        println!("Raw TCP backend running at tcp://0.0.0.0:{TCP_PORT} (first line is the room name)");
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let state = tcp_state.clone();
+            tokio::spawn(async move {
+                let (read, write) = stream.into_split();
+                let mut reader = BufReader::new(read).lines();
+                let Ok(Some(room)) = reader.next_line().await else {
+                    return;
+                };
+                let transport = TcpLineTransport {
+                    reader,
+                    writer: write,
+                };
+                handle_socket(transport, room, Codec::None, state).await;
+            });
+        }
+    });
+
     // Configure Axum web server with WebSocket support
     let app = Router::new()
-        // Register WebSocket handler for /ws endpoint
-        // When clients connect to ws://localhost:3001/ws, handle_ws function will be called
-        .route("/ws", get(handle_ws))
+        // Register WebSocket handler for /ws/:room endpoint
+        // When clients connect to ws://localhost:3001/ws/:room, handle_ws function will be called
+        .route("/ws/:room", get(handle_ws))
         // Share application state across all request handlers
         // This state will be available in the handle_ws function
-        .with_state((
-            tx.clone(), // Clone of broadcast sender - allows sending messages to all connected WebSocket clients
-            producer,   // Kafka producer instance - for publishing messages to Kafka topics
-            seen_messages, // Thread-safe set of processed message IDs - prevents duplicate processing
-        ));
+        .with_state(state);
 
     // Inform developer about server status
-    println!("WebSocket server running at ws://localhost:3001/ws");
+    println!("WebSocket server running at ws://localhost:3001/ws/:room");
 
     // Start the web server
     axum::serve(
@@ -111,62 +275,163 @@ async fn main() {
 
 
 /// WebSocket connection handler - upgrades HTTP requests to WebSocket connections
-/// This function is called when a client initiates a WebSocket handshake at /ws
+/// This function is called when a client initiates a WebSocket handshake at /ws/:room
 async fn handle_ws(
     // WebSocket upgrade request - provided by Axum automatically
     ws: WebSocketUpgrade,
+    // Room the client wants to join, taken from the URL path
+    Path(room): Path<String>,
+    // `?compress=zstd` / `?compress=gzip` advertises client support for
+    // compressed frames; anything else means uncompressed.
+    Query(params): Query<HashMap<String, String>>,
     // Extract shared application state from the router
-    State((tx, producer, seen_messages)): State<(
-        broadcast::Sender<String>,  // For broadcasting messages to all clients
-        FutureProducer,             // For sending messages to Kafka
-        Arc<Mutex<HashSet<String>>>, // For tracking processed message IDs
-    )>,
+    State(state): State<AppState>,
 ) -> impl IntoResponse {
+    let ws_codec = Codec::from_name(params.get("compress").map(String::as_str));
     // Complete the WebSocket handshake and spawn the actual connection handler
-    ws.on_upgrade(move |socket| handle_socket(socket, tx, producer, seen_messages))
+    ws.on_upgrade(move |socket| handle_socket(WebSocketTransport(socket), room, ws_codec, state))
 }
 
 
-pub async fn handle_socket(
-    socket: WebSocket,
-    tx: broadcast::Sender<String>,
-    producer: FutureProducer,
-    seen_messages: Arc<Mutex<HashSet<String>>>,
+/// Drives one connection's fan-in (client -> Kafka -> room) and fan-out
+/// (room -> client) loops. Generic over `Transport` so the same dedup /
+/// publish / broadcast logic serves both backends that exist today —
+/// WebSocket and raw TCP — without being duplicated; see
+/// [`kafka_rust_chat_backend::transport::Transport`] for why a WebTransport
+/// backend isn't one of them yet.
+pub async fn handle_socket<T: Transport>(
+    transport: T,
+    room: String,
+    ws_codec: Codec,
+    state: AppState,
 ) {
-    let (mut sender, mut receiver) = socket.split();
+    let (mut sender, mut receiver) = transport.split();
+    let tx = get_or_create_room(&state.rooms, &room);
     let mut rx = tx.subscribe();
 
+    // Every connection gets its own id, scoped to this connection rather
+    // than a global counter so restarts and concurrent connects can't
+    // collide.
+    let client_id = Uuid::new_v4();
+    let (direct_tx, mut direct_rx) = mpsc::unbounded_channel::<String>();
+    state.clients.lock().unwrap().insert(
+        client_id,
+        ClientHandle {
+            room: room.clone(),
+            name: None,
+            sender: direct_tx,
+        },
+    );
+    announce_join(&tx, &room, client_id, None, participant_count(&state.clients, &room));
+
+    let compression_config = state.compression;
     let send_task: JoinHandle<()> = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
-            if sender.send(Message::Text(msg)).await.is_err() {
+        loop {
+            let outgoing = tokio::select! {
+                msg = rx.recv() => {
+                    match msg {
+                        Ok(msg) => msg,
+                        Err(_) => break,
+                    }
+                }
+                Some(msg) = direct_rx.recv() => msg,
+            };
+            // Clients that never negotiated a codec keep getting plain
+            // text frames, unchanged from before compression existed.
+            let frame = if ws_codec == Codec::None {
+                Frame::Text(outgoing)
+            } else {
+                Frame::Binary(compression::compress(
+                    ws_codec,
+                    outgoing.as_bytes(),
+                    compression_config.min_size,
+                ))
+            };
+            if sender.send(frame).await.is_err() {
                 break;
             }
         }
     });
 
     let tx_clone = tx.clone();
-    let seen_clone = seen_messages.clone();
+    let producer = state.producer.clone();
+    let seen_clone = state.seen_messages.clone();
+    let clients_clone = state.clients.clone();
+    let compression_config = state.compression;
+    let topic = room_topic(&room);
+    let room_clone = room.clone();
     let recv_task: JoinHandle<()> = tokio::spawn(async move {
-        while let Some(Ok(Message::Text(text))) = receiver.next().await {
-            if let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&text) {
-                let id = Uuid::new_v4().to_string();
-                value["id"] = serde_json::Value::String(id.clone());
-                value["timestamp"] = serde_json::Value::String(Utc::now().to_rfc3339());
-                let payload = serde_json::to_string(&value).unwrap();
-
-                // Mark as seen before sending to Kafka
-                seen_clone.lock().unwrap().insert(id.clone());
-
-                // Send to Kafka
-                let _ = producer
-                    .send(
-                        FutureRecord::to("chat-room").payload(&payload).key("chat"),
-                        Duration::from_secs(0),
-                    )
-                    .await;
-
-                // Broadcast locally immediately
-                let _ = tx_clone.send(payload);
+        while let Some(frame) = receiver.recv().await {
+            let Some(text) = frame_to_text(frame) else {
+                continue;
+            };
+            match serde_json::from_str::<ClientMessage>(&text) {
+                Ok(ClientMessage::Chat { text }) => {
+                    let server_msg = ServerMessage::Chat {
+                        id: new_id(),
+                        timestamp: now_ts(),
+                        room: room_clone.clone(),
+                        text,
+                    };
+                    let payload = serde_json::to_string(&server_msg).unwrap();
+
+                    // Mark as seen before sending to Kafka
+                    seen_clone.lock().unwrap().insert_if_new(server_msg.id());
+
+                    // Send to Kafka, on this room's topic. Payloads at or
+                    // above the threshold are compressed on top of
+                    // librdkafka's own `compression.codec`.
+                    let kafka_payload = compression::compress(
+                        compression_config.kafka_codec,
+                        payload.as_bytes(),
+                        compression_config.min_size,
+                    );
+                    let _ = producer
+                        .send(
+                            FutureRecord::to(&topic).payload(&kafka_payload).key("chat"),
+                            Duration::from_secs(0),
+                        )
+                        .await;
+
+                    // Broadcast locally immediately
+                    let _ = tx_clone.send(payload);
+                }
+                Ok(ClientMessage::Typing) => {
+                    // Ephemeral — broadcast to the room only, never stored
+                    // in Kafka.
+                    let server_msg = ServerMessage::Typing {
+                        id: new_id(),
+                        timestamp: now_ts(),
+                        room: room_clone.clone(),
+                        client_id,
+                    };
+                    let _ = tx_clone.send(serde_json::to_string(&server_msg).unwrap());
+                }
+                Ok(ClientMessage::Join { name }) => {
+                    if let Some(handle) = clients_clone.lock().unwrap().get_mut(&client_id) {
+                        handle.name = Some(name.clone());
+                    }
+                    announce_join(
+                        &tx_clone,
+                        &room_clone,
+                        client_id,
+                        Some(name),
+                        participant_count(&clients_clone, &room_clone),
+                    );
+                }
+                Err(err) => {
+                    let error_msg = ServerMessage::Error {
+                        id: new_id(),
+                        timestamp: now_ts(),
+                        room: room_clone.clone(),
+                        message: err.to_string(),
+                    };
+                    send_to_client(
+                        &clients_clone,
+                        client_id,
+                        serde_json::to_string(&error_msg).unwrap(),
+                    );
+                }
             }
         }
     });
@@ -175,4 +440,33 @@ pub async fn handle_socket(
         _ = send_task => {},
         _ = recv_task => {},
     }
+
+    state.clients.lock().unwrap().remove(&client_id);
+    let leave_msg = ServerMessage::Leave {
+        id: new_id(),
+        timestamp: now_ts(),
+        room: room.clone(),
+        client_id,
+        participants: participant_count(&state.clients, &room),
+    };
+    let _ = tx.send(serde_json::to_string(&leave_msg).unwrap());
+}
+
+/// Broadcasts a `Join` presence event to everyone in the room.
+fn announce_join(
+    tx: &broadcast::Sender<String>,
+    room: &str,
+    client_id: Uuid,
+    name: Option<String>,
+    participants: usize,
+) {
+    let join_msg = ServerMessage::Join {
+        id: new_id(),
+        timestamp: now_ts(),
+        room: room.to_string(),
+        client_id,
+        name,
+        participants,
+    };
+    let _ = tx.send(serde_json::to_string(&join_msg).unwrap());
 }
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Messages a client may send over the wire. Deserializing through this
+/// type (rather than `serde_json::Value`) means malformed or unexpected
+/// shapes are rejected up front instead of silently dropped.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Chat { text: String },
+    Typing,
+    Join { name: String },
+}
+
+/// Messages the server sends out. `id`, `timestamp`, and `room` are always
+/// stamped by the server — a client can never forge them by including its
+/// own values in an outbound `Chat`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Chat {
+        id: String,
+        timestamp: String,
+        room: String,
+        text: String,
+    },
+    Typing {
+        id: String,
+        timestamp: String,
+        room: String,
+        client_id: Uuid,
+    },
+    Join {
+        id: String,
+        timestamp: String,
+        room: String,
+        client_id: Uuid,
+        name: Option<String>,
+        participants: usize,
+    },
+    Leave {
+        id: String,
+        timestamp: String,
+        room: String,
+        client_id: Uuid,
+        participants: usize,
+    },
+    Error {
+        id: String,
+        timestamp: String,
+        room: String,
+        message: String,
+    },
+}
+
+impl ServerMessage {
+    /// The server-stamped id every variant carries, used as the dedup key.
+    pub fn id(&self) -> &str {
+        match self {
+            ServerMessage::Chat { id, .. }
+            | ServerMessage::Typing { id, .. }
+            | ServerMessage::Join { id, .. }
+            | ServerMessage::Leave { id, .. }
+            | ServerMessage::Error { id, .. } => id,
+        }
+    }
+}
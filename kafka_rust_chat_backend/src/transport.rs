@@ -0,0 +1,169 @@
+use axum::extract::ws::{Message, WebSocket};
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use std::future::Future;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream,
+    },
+};
+
+/// One frame exchanged over a [`Transport`]: plain text, or binary (e.g. a
+/// compressed payload, tagged per [`crate::compression`]).
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+/// A bidirectional, frame-oriented connection that `handle_socket` can
+/// drive without caring about the underlying socket. Implemented for the
+/// WebSocket ([`WebSocketTransport`]) and raw-TCP ([`TcpLineTransport`])
+/// backends below. An HTTP/3 WebTransport backend was part of the original
+/// ask but is deliberately not implemented here — it needs a QUIC/HTTP-3
+/// stack this server doesn't otherwise run, which is a bigger lift than
+/// this trait itself; left for a follow-up rather than claimed and faked.
+pub trait Transport {
+    type Sender: TransportSender;
+    type Receiver: TransportReceiver;
+
+    /// Splits the transport into independent send/receive halves so the
+    /// fan-in and fan-out loops can run as separate tasks.
+    fn split(self) -> (Self::Sender, Self::Receiver);
+}
+
+/// The write half of a [`Transport`].
+///
+/// `handle_socket` spawns this behind `tokio::spawn`, which requires the
+/// returned future to be `Send`; plain `async fn` in a trait doesn't
+/// guarantee that; spelling it out as `-> impl Future<...> + Send` does.
+pub trait TransportSender: Send + 'static {
+    /// Sends one frame. Returns [`TransportError`] once the peer has gone
+    /// away.
+    fn send(&mut self, frame: Frame) -> impl Future<Output = Result<(), TransportError>> + Send;
+}
+
+/// The read half of a [`Transport`]. Returns `None` once the connection is
+/// closed; frame kinds other than text/binary are skipped rather than
+/// surfaced.
+///
+/// See [`TransportSender`] for why the returned future is spelled out as
+/// `Send` rather than left as a plain `async fn`.
+pub trait TransportReceiver: Send + 'static {
+    fn recv(&mut self) -> impl Future<Output = Option<Frame>> + Send;
+}
+
+/// The peer disconnected or the underlying transport failed.
+#[derive(Debug)]
+pub struct TransportError;
+
+/// [`Transport`] implementation backed by an Axum [`WebSocket`].
+pub struct WebSocketTransport(pub WebSocket);
+
+impl Transport for WebSocketTransport {
+    type Sender = WebSocketSender;
+    type Receiver = WebSocketReceiver;
+
+    fn split(self) -> (Self::Sender, Self::Receiver) {
+        let (sink, stream) = self.0.split();
+        (WebSocketSender(sink), WebSocketReceiver(stream))
+    }
+}
+
+pub struct WebSocketSender(SplitSink<WebSocket, Message>);
+
+impl TransportSender for WebSocketSender {
+    async fn send(&mut self, frame: Frame) -> Result<(), TransportError> {
+        let msg = match frame {
+            Frame::Text(text) => Message::Text(text),
+            Frame::Binary(bytes) => Message::Binary(bytes),
+        };
+        self.0.send(msg).await.map_err(|_| TransportError)
+    }
+}
+
+pub struct WebSocketReceiver(SplitStream<WebSocket>);
+
+impl TransportReceiver for WebSocketReceiver {
+    async fn recv(&mut self) -> Option<Frame> {
+        while let Some(Ok(msg)) = self.0.next().await {
+            match msg {
+                Message::Text(text) => return Some(Frame::Text(text)),
+                Message::Binary(bytes) => return Some(Frame::Binary(bytes)),
+                _ => continue,
+            }
+        }
+        None
+    }
+}
+
+/// [`Transport`] implementation backed by a plain TCP socket, one frame per
+/// newline-terminated line — simple enough to drive with `nc`/`telnet` or a
+/// bare CLI client, at the cost of only ever carrying text: a client never
+/// negotiates WS-style compression over this backend (see `main.rs`'s TCP
+/// accept loop, which always hands off with `Codec::None`), so in practice
+/// [`Frame::Binary`] is never asked of it; [`TcpLineSender::send`] rejects
+/// it outright rather than inventing a binary-safe line encoding nothing
+/// exercises.
+///
+/// Fields are `pub` rather than hidden behind a constructor because the
+/// binary's accept loop needs to consume the handshake line (the room
+/// name) off the read half *before* handing the rest of the connection to
+/// `handle_socket` — see [`new`](TcpLineTransport::new) for the common case
+/// where no such peek is needed.
+pub struct TcpLineTransport {
+    pub reader: Lines<BufReader<OwnedReadHalf>>,
+    pub writer: OwnedWriteHalf,
+}
+
+impl TcpLineTransport {
+    /// Wraps a freshly-accepted socket with no handshake line to peek.
+    pub fn new(stream: TcpStream) -> Self {
+        let (read, write) = stream.into_split();
+        Self {
+            reader: BufReader::new(read).lines(),
+            writer: write,
+        }
+    }
+}
+
+impl Transport for TcpLineTransport {
+    type Sender = TcpLineSender;
+    type Receiver = TcpLineReceiver;
+
+    fn split(self) -> (Self::Sender, Self::Receiver) {
+        (TcpLineSender(self.writer), TcpLineReceiver(self.reader))
+    }
+}
+
+pub struct TcpLineSender(OwnedWriteHalf);
+
+impl TransportSender for TcpLineSender {
+    async fn send(&mut self, frame: Frame) -> Result<(), TransportError> {
+        let Frame::Text(mut line) = frame else {
+            // See the type's doc comment: binary frames never reach this
+            // backend in practice, since it's always connected with
+            // `Codec::None`.
+            return Err(TransportError);
+        };
+        line.push('\n');
+        self.0
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|_| TransportError)
+    }
+}
+
+pub struct TcpLineReceiver(Lines<BufReader<OwnedReadHalf>>);
+
+impl TransportReceiver for TcpLineReceiver {
+    async fn recv(&mut self) -> Option<Frame> {
+        match self.0.next_line().await {
+            Ok(Some(line)) => Some(Frame::Text(line)),
+            _ => None,
+        }
+    }
+}